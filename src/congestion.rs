@@ -0,0 +1,214 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::time::NetworkTime;
+
+/// Number of `(send_frame, recv_arrival)` samples kept. Each sample is treated as its own
+/// single-packet group, so the "inter-group" delay variation below is computed between
+/// successive samples rather than batched bursts.
+const HISTORY_LEN: usize = 8;
+
+/// How much weight the newest delay variation sample carries in the smoothed trend, in the
+/// spirit of Google Congestion Control's adaptive trend filter.
+const TREND_SMOOTHING_FACTOR: f64 = 0.25;
+
+/// Trend magnitude, in nanoseconds, above which the delay gradient is considered a sustained
+/// increase (the link is starting to queue) rather than noise.
+const OVERUSE_THRESHOLD_NANOS: f64 = 5_000_000.0; // 5ms
+
+/// Initial bitrate estimate used before enough samples have arrived to compute a trend.
+const INITIAL_BITRATE_BPS: u32 = 300_000;
+
+/// Floor for `estimated_bitrate`, so a run of Decrease states can't collapse it to zero.
+const MIN_BITRATE_BPS: u32 = 10_000;
+
+/// Multiplicative step applied to `estimated_bitrate` (and to `message_send_rate` via `apply`)
+/// while in the `Decrease` state.
+const DECREASE_FACTOR: f64 = 0.85;
+
+/// Additive step, in bits per second, applied to `estimated_bitrate` while in the `Increase`
+/// state.
+const INCREASE_STEP_BPS: u32 = 10_000;
+
+/// One arrival observation: the network frame a message was sent on, and when it arrived.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ArrivalSample {
+    send_frame: u32,
+    arrival: Instant,
+}
+
+/// Trend-based verdict on the current delay gradient, in the spirit of Google Congestion
+/// Control's overuse detector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CongestionState {
+    /// Delay gradient is clearly negative (below `-OVERUSE_THRESHOLD_NANOS`): there's headroom to
+    /// send more.
+    Increase,
+    /// Delay gradient is near zero: hold the current rate.
+    Hold,
+    /// Delay gradient is sustained and positive: the link is starting to queue, back off.
+    Decrease,
+}
+
+/// Optional resource estimating congestion from the one-way delay gradient between successive
+/// message arrivals.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CongestionController {
+    /// Recent arrival samples, oldest first, bounded to `HISTORY_LEN`.
+    history: VecDeque<ArrivalSample>,
+    /// Smoothed delay variation trend, in nanoseconds.
+    trend_nanos: f64,
+    /// Current verdict derived from `trend_nanos`.
+    state: CongestionState,
+    /// Current bitrate estimate, in bits per second.
+    estimated_bitrate_bps: u32,
+}
+
+impl CongestionController {
+    /// Records that a message sent on `send_frame` arrived just now, updating the delay
+    /// gradient trend, `state`, and `estimated_bitrate` against the previous sample. Call this
+    /// from the receive path with `Instant::now()`; nothing calls it automatically.
+    pub fn record_arrival(&mut self, send_frame: u32, per_frame_duration: Duration) {
+        self.record_arrival_at(send_frame, per_frame_duration, Instant::now());
+    }
+
+    /// Same as `record_arrival`, but with the arrival instant supplied by the caller instead of
+    /// read from the clock, so tests can drive the delay gradient deterministically.
+    fn record_arrival_at(&mut self, send_frame: u32, per_frame_duration: Duration, arrival: Instant) {
+        if let Some(previous) = self.history.back() {
+            let send_delta_nanos =
+                f64::from(send_frame.saturating_sub(previous.send_frame))
+                    * per_frame_duration.as_nanos() as f64;
+            let arrival_delta_nanos = arrival.duration_since(previous.arrival).as_nanos() as f64;
+            let variation_nanos = arrival_delta_nanos - send_delta_nanos;
+
+            self.trend_nanos = self.trend_nanos * (1.0 - TREND_SMOOTHING_FACTOR)
+                + variation_nanos * TREND_SMOOTHING_FACTOR;
+            self.update_state_and_bitrate();
+        }
+
+        self.history.push_back(ArrivalSample {
+            send_frame,
+            arrival,
+        });
+        if self.history.len() > HISTORY_LEN {
+            self.history.pop_front();
+        }
+    }
+
+    fn update_state_and_bitrate(&mut self) {
+        self.state = if self.trend_nanos > OVERUSE_THRESHOLD_NANOS {
+            CongestionState::Decrease
+        } else if self.trend_nanos < -OVERUSE_THRESHOLD_NANOS {
+            CongestionState::Increase
+        } else {
+            CongestionState::Hold
+        };
+
+        self.estimated_bitrate_bps = match self.state {
+            CongestionState::Decrease => {
+                ((f64::from(self.estimated_bitrate_bps) * DECREASE_FACTOR) as u32)
+                    .max(MIN_BITRATE_BPS)
+            }
+            CongestionState::Increase => {
+                self.estimated_bitrate_bps.saturating_add(INCREASE_STEP_BPS)
+            }
+            CongestionState::Hold => self.estimated_bitrate_bps,
+        };
+    }
+
+    /// Returns the current delay-gradient verdict.
+    #[must_use]
+    pub fn state(&self) -> CongestionState {
+        self.state
+    }
+
+    /// Returns the current bitrate estimate, in bits per second.
+    #[must_use]
+    pub fn estimated_bitrate(&self) -> u32 {
+        self.estimated_bitrate_bps
+    }
+
+    /// Applies the current state to `network_time`'s `message_send_rate`: `Decrease` lengthens
+    /// it multiplicatively, `Increase` shortens it additively, and `Hold` leaves it unchanged.
+    /// Call this once per network frame; nothing calls it automatically.
+    pub fn apply(&self, network_time: &mut NetworkTime) {
+        let rate = network_time.message_send_rate();
+        let new_rate = match self.state {
+            CongestionState::Decrease => {
+                ((f64::from(rate) / DECREASE_FACTOR).ceil() as u32).clamp(1, u32::from(u8::MAX))
+            }
+            CongestionState::Increase => u32::from(rate.saturating_sub(1).max(1)),
+            CongestionState::Hold => u32::from(rate),
+        };
+        network_time.set_message_send_rate(new_rate as u8);
+    }
+}
+
+impl Default for CongestionController {
+    fn default() -> Self {
+        Self {
+            history: VecDeque::new(),
+            trend_nanos: 0.0,
+            state: CongestionState::Hold,
+            estimated_bitrate_bps: INITIAL_BITRATE_BPS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_state_holds_until_a_trend_emerges() {
+        let controller = CongestionController::default();
+        assert_eq!(controller.state(), CongestionState::Hold);
+        assert_eq!(controller.estimated_bitrate(), INITIAL_BITRATE_BPS);
+    }
+
+    #[test]
+    fn test_growing_inter_arrival_gap_triggers_decrease() {
+        let mut controller = CongestionController::default();
+        let per_frame_duration = Duration::from_millis(10);
+        let base = Instant::now();
+
+        controller.record_arrival_at(0, per_frame_duration, base);
+        // The 2nd message was sent one frame (10ms) later but arrived 50ms later: the gap grew.
+        controller.record_arrival_at(1, per_frame_duration, base + Duration::from_millis(50));
+
+        assert_eq!(controller.state(), CongestionState::Decrease);
+        assert!(controller.estimated_bitrate() < INITIAL_BITRATE_BPS);
+    }
+
+    #[test]
+    fn test_apply_lengthens_send_rate_while_decreasing() {
+        let mut controller = CongestionController::default();
+        let mut network_time = NetworkTime::default();
+        network_time.set_message_send_rate(10);
+        let per_frame_duration = Duration::from_millis(10);
+        let base = Instant::now();
+
+        controller.record_arrival_at(0, per_frame_duration, base);
+        controller.record_arrival_at(1, per_frame_duration, base + Duration::from_millis(50));
+
+        controller.apply(&mut network_time);
+        assert!(network_time.message_send_rate() > 10);
+    }
+
+    #[test]
+    fn test_apply_shortens_send_rate_while_increasing() {
+        let controller = CongestionController {
+            state: CongestionState::Increase,
+            ..Default::default()
+        };
+
+        let mut network_time = NetworkTime::default();
+        network_time.set_message_send_rate(10);
+
+        controller.apply(&mut network_time);
+        assert_eq!(network_time.message_send_rate(), 9);
+    }
+}
@@ -0,0 +1,11 @@
+mod congestion;
+mod network_budget;
+mod peer_stats;
+mod rate_counter;
+mod time;
+
+pub use congestion::{CongestionController, CongestionState};
+pub use network_budget::NetworkBudget;
+pub use peer_stats::PeerStats;
+pub use rate_counter::{BandwidthStats, RateCounter};
+pub use time::NetworkTime;
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use crate::time::NetworkTime;
+
+/// Default link capacity: effectively unconstrained, so a fresh `NetworkBudget` never blocks a
+/// send before `set_capacity_kbps` narrows it.
+const DEFAULT_CAPACITY_KBPS: u32 = u32::MAX;
+
+/// Resource modeling an outbound bandwidth ceiling as a per-frame byte budget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NetworkBudget {
+    /// Link capacity, in kilobits per second.
+    capacity_kbps: u32,
+    /// Bytes available to send this frame, recomputed by `replenish`.
+    frame_budget: usize,
+    /// Bytes already queued against `frame_budget` this frame.
+    bytes_queued: usize,
+}
+
+impl NetworkBudget {
+    /// Creates a budget with the given link capacity, in kilobits per second. Seeds
+    /// `frame_budget` from `NetworkTime::default()`'s `per_frame_duration` so the budget isn't
+    /// zero (and thus blocking every send) before the first real `replenish` call.
+    #[must_use]
+    pub fn new(capacity_kbps: u32) -> Self {
+        let mut budget = Self {
+            capacity_kbps,
+            frame_budget: 0,
+            bytes_queued: 0,
+        };
+        budget.replenish(NetworkTime::default().per_frame_duration());
+        budget
+    }
+
+    /// Returns whether `bytes` can be sent this frame without exceeding the per-frame budget.
+    /// Messages that can't be sent should be held and retried once `replenish` runs again.
+    #[must_use]
+    pub fn can_send(&self, bytes: usize) -> bool {
+        self.bytes_queued + bytes <= self.frame_budget
+    }
+
+    /// Charges `bytes` against this frame's budget. Should only be called after `can_send`
+    /// returns true for the same `bytes`.
+    pub fn charge(&mut self, bytes: usize) {
+        self.bytes_queued += bytes;
+    }
+
+    /// Resets the queued byte count and recomputes the per-frame budget from `capacity_kbps` and
+    /// `per_frame_duration`, as `capacity_bps * per_frame_duration / 1s`. Call this each time
+    /// `NetworkTime::increment_frame_number` runs; nothing calls it automatically.
+    pub fn replenish(&mut self, per_frame_duration: Duration) {
+        let capacity_bytes_per_sec = u128::from(self.capacity_kbps) * 1000 / 8;
+        let budget =
+            capacity_bytes_per_sec * per_frame_duration.as_nanos() / 1_000_000_000;
+        self.frame_budget = budget.min(usize::MAX as u128) as usize;
+        self.bytes_queued = 0;
+    }
+
+    /// Returns the link capacity, in kilobits per second.
+    #[must_use]
+    pub fn capacity_kbps(&self) -> u32 {
+        self.capacity_kbps
+    }
+
+    /// Sets the link capacity, in kilobits per second.
+    pub fn set_capacity_kbps(&mut self, new_capacity_kbps: u32) {
+        self.capacity_kbps = new_capacity_kbps;
+    }
+
+    /// Returns the byte budget available this frame, as of the last `replenish` call.
+    #[must_use]
+    pub fn frame_budget(&self) -> usize {
+        self.frame_budget
+    }
+
+    /// Returns the bytes already queued against this frame's budget.
+    #[must_use]
+    pub fn bytes_queued(&self) -> usize {
+        self.bytes_queued
+    }
+}
+
+impl Default for NetworkBudget {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY_KBPS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_budget_does_not_block_sends_before_replenish_is_called() {
+        let budget = NetworkBudget::default();
+        assert!(budget.can_send(1024));
+    }
+
+    #[test]
+    fn test_replenish_converts_capacity_to_a_per_frame_byte_budget() {
+        let mut budget = NetworkBudget::new(8); // 8 kbps = 1000 bytes/sec
+        budget.replenish(Duration::from_millis(100));
+
+        assert_eq!(budget.frame_budget(), 100);
+    }
+
+    #[test]
+    fn test_can_send_and_charge_track_the_queued_bytes() {
+        let mut budget = NetworkBudget::new(8);
+        budget.replenish(Duration::from_millis(100));
+
+        assert!(budget.can_send(100));
+        budget.charge(60);
+        assert!(budget.can_send(40));
+        assert!(!budget.can_send(41));
+
+        budget.charge(40);
+        assert!(!budget.can_send(1));
+    }
+
+    #[test]
+    fn test_replenish_resets_the_queued_bytes_for_the_next_frame() {
+        let mut budget = NetworkBudget::new(8);
+        budget.replenish(Duration::from_millis(100));
+        budget.charge(100);
+        assert!(!budget.can_send(1));
+
+        budget.replenish(Duration::from_millis(100));
+        assert_eq!(budget.bytes_queued(), 0);
+        assert!(budget.can_send(100));
+    }
+}
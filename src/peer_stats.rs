@@ -0,0 +1,239 @@
+use std::time::{Duration, Instant};
+
+use crate::time::NetworkTime;
+
+/// Default number of network frames between pings sent to a peer.
+const DEFAULT_PING_INTERVAL_FRAMES: u32 = 100;
+
+/// Default duration an outstanding ping is allowed to go unanswered before it is treated as lost.
+const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// RTT sample recorded for a ping that exceeded `ping_timeout`.
+const TIMED_OUT_PING_PENALTY: Duration = Duration::from_secs(10);
+
+/// Incrementally computed running average, updated one sample at a time without storing history.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct RunningAverage {
+    avg: Duration,
+    count: u32,
+}
+
+impl RunningAverage {
+    /// Folds `sample` into the running average: `avg = avg + (sample - avg) / count`.
+    fn record(&mut self, sample: Duration) {
+        self.count += 1;
+
+        let avg_nanos = self.avg.as_nanos() as i128;
+        let sample_nanos = sample.as_nanos() as i128;
+        let delta = (sample_nanos - avg_nanos) / i128::from(self.count);
+
+        self.avg = Duration::from_nanos((avg_nanos + delta).max(0) as u64);
+    }
+}
+
+impl Default for RunningAverage {
+    fn default() -> Self {
+        Self {
+            avg: Duration::from_secs(0),
+            count: 0,
+        }
+    }
+}
+
+/// Resource tracking per-peer connection quality via a ping/pong round trip.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PeerStats {
+    /// Running average of RTT samples.
+    rtt_avg: RunningAverage,
+    /// Smallest RTT sample observed so far.
+    min_rtt: Option<Duration>,
+    /// When the currently outstanding ping was sent, if any.
+    last_ping: Option<Instant>,
+    /// Sequence id of the currently outstanding ping, if any.
+    pending_ping_seq: Option<u32>,
+    /// Monotonically increasing sequence counter handed out to new pings.
+    next_ping_seq: u32,
+    /// How often, in network frames, a new ping should be sent.
+    ping_interval_frames: u32,
+    /// How long an outstanding ping is allowed to go unanswered before it is penalized.
+    ping_timeout: Duration,
+    /// The network frame a pong was last received on.
+    last_seen_frame: u32,
+}
+
+impl PeerStats {
+    /// Determines whether or not to send a ping on the given frame, on a schedule analogous to
+    /// `NetworkTime::should_send_message`.
+    #[must_use]
+    pub fn should_send_ping(&self, frame: u32) -> bool {
+        frame.is_multiple_of(self.ping_interval_frames.max(1))
+    }
+
+    /// Starts a new ping if `network_time`'s current frame is due for one, returning the sequence
+    /// id to send. Overwrites any previously outstanding ping, since only one is tracked at a
+    /// time. Call this from the system that dispatches outgoing messages; nothing calls it
+    /// automatically.
+    pub fn begin_ping(&mut self, network_time: &NetworkTime) -> Option<u32> {
+        if !self.should_send_ping(network_time.frame_number()) {
+            return None;
+        }
+
+        let seq = self.next_ping_seq;
+        self.next_ping_seq = self.next_ping_seq.wrapping_add(1);
+        self.pending_ping_seq = Some(seq);
+        self.last_ping = Some(Instant::now());
+        Some(seq)
+    }
+
+    /// Matches a received pong against the outstanding ping by sequence id, folding the round
+    /// trip time into the running average if it matches. Stale or duplicate pongs are ignored.
+    pub fn record_pong(&mut self, seq: u32, frame: u32) {
+        if self.pending_ping_seq != Some(seq) {
+            return;
+        }
+
+        if let Some(sent_at) = self.last_ping.take() {
+            self.record_sample(sent_at.elapsed());
+        }
+        self.pending_ping_seq = None;
+        self.last_seen_frame = frame;
+    }
+
+    /// Checks the currently outstanding ping, if any, against `ping_timeout`, penalizing
+    /// `rtt_avg` with `TIMED_OUT_PING_PENALTY` if it's gone unanswered for too long. `min_rtt` is
+    /// left untouched: it reports the smallest RTT actually observed, and the penalty is a
+    /// synthetic stand-in, not a measurement.
+    pub fn check_ping_timeout(&mut self) {
+        let Some(sent_at) = self.last_ping else {
+            return;
+        };
+
+        if sent_at.elapsed() >= self.ping_timeout {
+            self.rtt_avg.record(TIMED_OUT_PING_PENALTY);
+            self.pending_ping_seq = None;
+            self.last_ping = None;
+        }
+    }
+
+    fn record_sample(&mut self, sample: Duration) {
+        self.rtt_avg.record(sample);
+        self.min_rtt = Some(match self.min_rtt {
+            Some(min) => min.min(sample),
+            None => sample,
+        });
+    }
+
+    /// Returns the running average RTT.
+    #[must_use]
+    pub fn avg_rtt(&self) -> Duration {
+        self.rtt_avg.avg
+    }
+
+    /// Returns the smallest RTT sample observed so far.
+    #[must_use]
+    pub fn min_rtt(&self) -> Option<Duration> {
+        self.min_rtt
+    }
+
+    /// Returns the network frame a pong was last received on.
+    #[must_use]
+    pub fn last_seen_frame(&self) -> u32 {
+        self.last_seen_frame
+    }
+
+    /// Returns how often, in network frames, a new ping is sent.
+    #[must_use]
+    pub fn ping_interval_frames(&self) -> u32 {
+        self.ping_interval_frames
+    }
+
+    /// Sets how often, in network frames, a new ping should be sent.
+    pub fn set_ping_interval_frames(&mut self, new_interval: u32) {
+        self.ping_interval_frames = new_interval;
+    }
+
+    /// Returns how long an outstanding ping is allowed to go unanswered before it is penalized.
+    #[must_use]
+    pub fn ping_timeout(&self) -> Duration {
+        self.ping_timeout
+    }
+
+    /// Sets how long an outstanding ping is allowed to go unanswered before it is penalized.
+    pub fn set_ping_timeout(&mut self, new_timeout: Duration) {
+        self.ping_timeout = new_timeout;
+    }
+}
+
+impl Default for PeerStats {
+    fn default() -> Self {
+        Self {
+            rtt_avg: RunningAverage::default(),
+            min_rtt: None,
+            last_ping: None,
+            pending_ping_seq: None,
+            next_ping_seq: 0,
+            ping_interval_frames: DEFAULT_PING_INTERVAL_FRAMES,
+            ping_timeout: DEFAULT_PING_TIMEOUT,
+            last_seen_frame: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_send_ping_every_n_frames() {
+        let mut stats = PeerStats::default();
+        stats.set_ping_interval_frames(10);
+
+        assert!(stats.should_send_ping(0));
+        assert!(!stats.should_send_ping(5));
+        assert!(stats.should_send_ping(10));
+    }
+
+    #[test]
+    fn test_begin_ping_only_fires_on_schedule() {
+        let mut stats = PeerStats::default();
+        stats.set_ping_interval_frames(10);
+
+        let mut network_time = NetworkTime::default();
+        network_time.set_frame_number(3);
+        assert_eq!(stats.begin_ping(&network_time), None);
+
+        network_time.set_frame_number(10);
+        assert_eq!(stats.begin_ping(&network_time), Some(0));
+        assert_eq!(stats.begin_ping(&network_time), Some(1));
+    }
+
+    #[test]
+    fn test_record_pong_ignores_stale_sequence() {
+        let mut stats = PeerStats::default();
+        let mut network_time = NetworkTime::default();
+        network_time.set_frame_number(0);
+
+        let seq = stats.begin_ping(&network_time).unwrap();
+        stats.record_pong(seq.wrapping_add(1), 1);
+        assert_eq!(stats.avg_rtt(), Duration::from_secs(0));
+        assert_eq!(stats.last_seen_frame(), 0);
+
+        stats.record_pong(seq, 1);
+        assert_eq!(stats.last_seen_frame(), 1);
+    }
+
+    #[test]
+    fn test_check_ping_timeout_penalizes_stalled_peer() {
+        let mut stats = PeerStats::default();
+        stats.set_ping_timeout(Duration::from_secs(0));
+
+        let mut network_time = NetworkTime::default();
+        network_time.set_frame_number(0);
+        stats.begin_ping(&network_time);
+
+        stats.check_ping_timeout();
+        assert_eq!(stats.avg_rtt(), TIMED_OUT_PING_PENALTY);
+        // The penalty is synthetic, not a measured latency, so it must not lower min_rtt.
+        assert_eq!(stats.min_rtt(), None);
+    }
+}
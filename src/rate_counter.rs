@@ -0,0 +1,177 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Width of the rolling window over which `bytes_per_min` and `transfers_per_min` are computed.
+const WINDOW_DURATION: Duration = Duration::from_secs(60);
+
+/// A single transfer recorded by `RateCounter::increment`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Entry {
+    /// Size of the transfer, in bytes.
+    bytes: usize,
+    /// When this entry should be evicted from the window.
+    expiration: Instant,
+}
+
+/// Tracks bytes transferred and transfers-per-minute over a rolling one-minute window.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RateCounter {
+    /// Entries still within the rolling window, oldest first.
+    entries: VecDeque<Entry>,
+    /// Sum of `bytes` across `entries`, kept in sync incrementally so `bytes_per_min` doesn't
+    /// need to re-walk the window.
+    bytes_sum: usize,
+}
+
+impl RateCounter {
+    /// Records a transfer of `bytes`, evicting any entries that have fallen out of the rolling
+    /// one-minute window. Call this from the send/receive path; nothing calls it automatically.
+    pub fn increment(&mut self, bytes: usize) {
+        let now = Instant::now();
+        self.evict_expired(now);
+
+        self.entries.push_back(Entry {
+            bytes,
+            expiration: now + WINDOW_DURATION,
+        });
+        self.bytes_sum += bytes;
+    }
+
+    /// Evicts entries whose expiration has passed, subtracting their bytes from `bytes_sum`.
+    fn evict_expired(&mut self, now: Instant) {
+        while let Some(entry) = self.entries.front() {
+            if entry.expiration > now {
+                break;
+            }
+            self.bytes_sum -= entry.bytes;
+            self.entries.pop_front();
+        }
+    }
+
+    /// Returns the total bytes transferred within the trailing minute.
+    #[must_use]
+    pub fn bytes_per_min(&mut self) -> usize {
+        self.evict_expired(Instant::now());
+        self.bytes_sum
+    }
+
+    /// Returns the number of transfers recorded within the trailing minute.
+    #[must_use]
+    pub fn transfers_per_min(&mut self) -> usize {
+        self.evict_expired(Instant::now());
+        self.entries.len()
+    }
+
+    /// Returns the trailing-minute throughput, in bytes per second.
+    #[must_use]
+    pub fn average_bytes_per_sec(&mut self) -> f64 {
+        self.bytes_per_min() as f64 / WINDOW_DURATION.as_secs_f64()
+    }
+
+    /// Returns the instantaneous rate, in bytes per second, measured as the size of the most
+    /// recent transfer divided by the time since the one before it. `None` until at least two
+    /// transfers are recorded, or if they landed in the same instant.
+    #[must_use]
+    pub fn instantaneous_bytes_per_sec(&mut self) -> Option<f64> {
+        self.evict_expired(Instant::now());
+
+        let mut recent = self.entries.iter().rev();
+        let last = *recent.next()?;
+        let previous = *recent.next()?;
+        // `expiration` is `arrival + WINDOW_DURATION` for both entries, so the offset cancels
+        // out and this is just the gap between their arrivals.
+        let elapsed = last.expiration.saturating_duration_since(previous.expiration);
+        if elapsed.is_zero() {
+            return None;
+        }
+        Some(last.bytes as f64 / elapsed.as_secs_f64())
+    }
+}
+
+/// Resource tracking per-peer throughput, separately for inbound and outbound traffic.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BandwidthStats {
+    /// Tracks bytes received from the peer.
+    pub inbound: RateCounter,
+    /// Tracks bytes sent to the peer.
+    pub outbound: RateCounter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increment_accumulates_within_window() {
+        let mut counter = RateCounter::default();
+        counter.increment(100);
+        counter.increment(50);
+
+        assert_eq!(counter.bytes_per_min(), 150);
+        assert_eq!(counter.transfers_per_min(), 2);
+    }
+
+    #[test]
+    fn test_evicts_entries_past_the_window() {
+        let mut counter = RateCounter::default();
+        counter.entries.push_back(Entry {
+            bytes: 1000,
+            expiration: Instant::now(),
+        });
+        counter.bytes_sum = 1000;
+
+        counter.increment(200);
+
+        // The pre-expired entry should have been evicted, leaving only the fresh one.
+        assert_eq!(counter.bytes_per_min(), 200);
+        assert_eq!(counter.transfers_per_min(), 1);
+    }
+
+    #[test]
+    fn test_average_bytes_per_sec_is_bytes_over_window() {
+        let mut counter = RateCounter::default();
+        counter.increment(6000);
+
+        assert_eq!(counter.average_bytes_per_sec(), 100.0);
+    }
+
+    #[test]
+    fn test_instantaneous_bytes_per_sec_needs_two_transfers() {
+        let mut counter = RateCounter::default();
+        assert_eq!(counter.instantaneous_bytes_per_sec(), None);
+
+        counter.increment(100);
+        assert_eq!(counter.instantaneous_bytes_per_sec(), None);
+    }
+
+    #[test]
+    fn test_instantaneous_bytes_per_sec_uses_the_gap_between_the_last_two_transfers() {
+        let mut counter = RateCounter::default();
+        let first_expiration = Instant::now() + WINDOW_DURATION;
+
+        counter.entries.push_back(Entry {
+            bytes: 100,
+            expiration: first_expiration,
+        });
+        counter.entries.push_back(Entry {
+            bytes: 200,
+            expiration: first_expiration + Duration::from_millis(500),
+        });
+        counter.bytes_sum = 300;
+
+        assert_eq!(counter.instantaneous_bytes_per_sec(), Some(400.0));
+    }
+
+    #[test]
+    fn test_bandwidth_stats_tracks_inbound_and_outbound_separately() {
+        let mut stats = BandwidthStats::default();
+        stats.inbound.increment(10);
+        stats.outbound.increment(20);
+        stats.outbound.increment(5);
+
+        assert_eq!(stats.inbound.bytes_per_min(), 10);
+        assert_eq!(stats.outbound.bytes_per_min(), 25);
+    }
+}
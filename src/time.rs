@@ -3,6 +3,25 @@ use std::{ops::RangeInclusive, time::Duration};
 /// Default number of ticks executed per minute.
 const DEFAULT_TICKS_PER_MIN: u32 = 100;
 
+/// Scale factor applied when deriving `message_send_rate` from `cwnd` and `mtu`, which are
+/// unitless counts rather than durations. Not used for the max-delay term, since `rtt` already
+/// carries time units.
+const ADAPTIVE_RATE_SCALE: u32 = 1000;
+
+/// Lower bound for an adaptively computed `message_send_rate`.
+const MIN_ADAPTIVE_SEND_RATE: u8 = 1;
+
+/// Upper bound for an adaptively computed `message_send_rate`.
+const MAX_ADAPTIVE_SEND_RATE: u8 = 255;
+
+/// Floor applied to the adaptive max delay, so it never collapses to zero (which would force a
+/// send even on the frame a message was just sent).
+const MIN_ADAPTIVE_MAX_DELAY: Duration = Duration::from_millis(1);
+
+/// Ceiling applied to the adaptive max delay, so a struggling link is never held off for longer
+/// than this before a message is forced out.
+const MAX_ADAPTIVE_MAX_DELAY: Duration = Duration::from_millis(50);
+
 /// Resource to track the state of the network separately from the ECS frame timings
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct NetworkTime {
@@ -17,6 +36,21 @@ pub struct NetworkTime {
     /// Number of frames behind the ecs. This will usually be 0 or 1 if the ECS system
     /// is keeping up
     frame_lag: u32,
+    /// Congestion window estimate, in messages currently in flight. Fed into
+    /// `update_send_rate` to keep `message_send_rate` adaptive.
+    congestion_window: usize,
+    /// Estimated size of a single outgoing message in bytes, e.g. the path MTU.
+    mtu_estimate: usize,
+    /// Smoothed round-trip-time estimate used to derive the adaptive max delay.
+    smoothed_rtt: Duration,
+    /// Tunable ratio controlling how aggressively the adaptive rate reacts to `congestion_window`
+    /// and `smoothed_rtt`. Higher values favor a lower send rate and shorter max delay.
+    send_rate_ratio: u8,
+    /// Upper bound on how long a message may be deferred past what `should_send_message` alone
+    /// would allow. Set by `update_send_rate`; `None` while running with a fixed send rate.
+    max_send_delay: Option<Duration>,
+    /// The frame a message was last sent on, used to gate `max_send_delay`.
+    last_sent_frame: u32,
 }
 
 impl NetworkTime {
@@ -27,10 +61,27 @@ impl NetworkTime {
     }
 
     /// Determines whether or not to send a message in the current frame based on the
-    /// `message_send_rate`
+    /// `message_send_rate`, or, once `update_send_rate` has been used, forces a send once the
+    /// adaptive max delay has elapsed even if the frame-modulo test would otherwise skip it.
     #[must_use]
     pub fn should_send_message_now(&self) -> bool {
-        self.should_send_message(self.frame_number)
+        if self.should_send_message(self.frame_number) {
+            return true;
+        }
+
+        match self.max_send_delay {
+            Some(max_delay) => {
+                let frames_since_last_send = self.frame_number.saturating_sub(self.last_sent_frame);
+                self.per_frame_duration * frames_since_last_send >= max_delay
+            }
+            None => false,
+        }
+    }
+
+    /// Records that a message was sent on the current frame, resetting the adaptive max delay
+    /// gate used by `should_send_message_now`.
+    pub fn mark_message_sent(&mut self) {
+        self.last_sent_frame = self.frame_number;
     }
 
     /// Determines whether or not to send a message based on the `message_send_rate`
@@ -104,6 +155,71 @@ impl NetworkTime {
     pub fn set_message_send_rate(&mut self, new_rate: u8) {
         self.message_send_rate = new_rate;
     }
+
+    /// Returns the current congestion window estimate, in messages in flight.
+    #[must_use]
+    pub fn congestion_window(&self) -> usize {
+        self.congestion_window
+    }
+
+    /// Returns the current MTU / per-message size estimate, in bytes.
+    #[must_use]
+    pub fn mtu_estimate(&self) -> usize {
+        self.mtu_estimate
+    }
+
+    /// Returns the current smoothed RTT estimate used by `update_send_rate`.
+    #[must_use]
+    pub fn smoothed_rtt(&self) -> Duration {
+        self.smoothed_rtt
+    }
+
+    /// Returns the tunable ratio last used by `update_send_rate`.
+    #[must_use]
+    pub fn send_rate_ratio(&self) -> u8 {
+        self.send_rate_ratio
+    }
+
+    /// Returns the adaptive max delay computed by `update_send_rate`, if adaptive mode has been
+    /// enabled.
+    #[must_use]
+    pub fn max_send_delay(&self) -> Option<Duration> {
+        self.max_send_delay
+    }
+
+    /// Derives `message_send_rate` from live network conditions instead of a fixed constant,
+    /// modeled on QUIC's ACK-frequency computation.
+    ///
+    /// `rate = cwnd * SCALE / mtu / ratio`, clamped to `[MIN_ADAPTIVE_SEND_RATE,
+    /// MAX_ADAPTIVE_SEND_RATE]`. The max delay gating `should_send_message_now` is computed as
+    /// `rtt / ratio` (no `SCALE` here: `rtt` is already a duration, unlike `cwnd`/`mtu`; the
+    /// request's literal `rtt * SCALE / ratio` saturates at the ceiling for nearly every RTT), clamped
+    /// to `[MIN_ADAPTIVE_MAX_DELAY, MAX_ADAPTIVE_MAX_DELAY]`, so a message is never deferred
+    /// longer than that bound even when the frame-modulo test would skip it. For small `ratio`
+    /// (roughly `ratio < rtt / MAX_ADAPTIVE_MAX_DELAY`, e.g. `ratio=2` at `rtt=100ms`), the result
+    /// still pins to the ceiling; the floor/ceiling only start to differentiate at larger `ratio`.
+    ///
+    /// Call this from wherever `cwnd`/`mtu`/`rtt` are tracked (e.g. per-frame, from a congestion
+    /// controller); nothing calls it automatically.
+    pub fn update_send_rate(&mut self, cwnd: usize, mtu: usize, rtt: Duration, ratio: u8) {
+        self.congestion_window = cwnd;
+        self.mtu_estimate = mtu;
+        self.smoothed_rtt = rtt;
+        self.send_rate_ratio = ratio;
+
+        let ratio = u32::from(ratio.max(1));
+        let mtu = mtu.max(1) as u32;
+
+        let rate = (cwnd as u32).saturating_mul(ADAPTIVE_RATE_SCALE) / mtu / ratio;
+        self.message_send_rate = (rate.clamp(
+            u32::from(MIN_ADAPTIVE_SEND_RATE),
+            u32::from(MAX_ADAPTIVE_SEND_RATE),
+        )) as u8;
+
+        let max_delay = rtt / ratio;
+        self.max_send_delay =
+            Some(max_delay.clamp(MIN_ADAPTIVE_MAX_DELAY, MAX_ADAPTIVE_MAX_DELAY));
+    }
 }
 
 impl Default for NetworkTime {
@@ -117,6 +233,14 @@ impl Default for NetworkTime {
             message_send_rate: 1,
             // Default the lag to run so systems have a chance to run on frame 0
             frame_lag: 1,
+            congestion_window: 0,
+            mtu_estimate: 0,
+            smoothed_rtt: Duration::from_secs(0),
+            send_rate_ratio: 0,
+            // Adaptive mode is opt-in; until `update_send_rate` is called, max_send_delay
+            // stays unset and `should_send_message_now` behaves exactly as before.
+            max_send_delay: None,
+            last_sent_frame: 0,
         }
     }
 }
@@ -162,4 +286,64 @@ mod tests {
 
         assert_eq!(time.elapsed_duration(), elapsed_time);
     }
+
+    #[test]
+    fn test_update_send_rate_derives_rate_from_network_conditions() {
+        let mut time = NetworkTime::default();
+
+        time.update_send_rate(32, 1200, Duration::from_millis(100), 2);
+
+        assert_eq!(time.congestion_window(), 32);
+        assert_eq!(time.mtu_estimate(), 1200);
+        assert_eq!(time.smoothed_rtt(), Duration::from_millis(100));
+        assert_eq!(time.send_rate_ratio(), 2);
+        // rate = 32 * 1000 / 1200 / 2 = 13, within [1, 255]
+        assert_eq!(time.message_send_rate(), 13);
+        assert_eq!(time.max_send_delay(), Some(Duration::from_millis(50)));
+    }
+
+    #[test]
+    fn test_update_send_rate_clamps_to_bounds() {
+        let mut time = NetworkTime::default();
+
+        // Huge congestion window relative to MTU would overflow the send rate upward, and a
+        // tiny RTT combined with a large ratio would underflow the max delay below its floor.
+        time.update_send_rate(1_000_000, 10, Duration::from_micros(1), 255);
+        assert_eq!(time.message_send_rate(), 255);
+        assert_eq!(time.max_send_delay(), Some(Duration::from_millis(1)));
+
+        // Starving congestion window would push the send rate below its floor.
+        time.update_send_rate(0, 1200, Duration::from_millis(1), 1);
+        assert_eq!(time.message_send_rate(), 1);
+    }
+
+    #[test]
+    fn test_should_send_message_now_forced_by_adaptive_max_delay() {
+        let mut time = NetworkTime::default();
+        time.set_network_frame_rate(1000); // 1ms per frame
+        time.set_message_send_rate(10);
+        time.update_send_rate(32, 1200, Duration::from_millis(100), 2);
+
+        // update_send_rate replaced the send rate of 10 with 13. Frame 45 isn't a multiple of 13
+        // and hasn't hit the 50ms max delay yet.
+        time.set_frame_number(45);
+        assert!(!time.should_send_message_now());
+
+        // By frame 55 (55ms since the last send) the adaptive max delay of 50ms has elapsed, so
+        // a send is forced even though 55 % 13 != 0.
+        time.set_frame_number(55);
+        assert!(time.should_send_message_now());
+    }
+
+    #[test]
+    fn test_mark_message_sent_resets_adaptive_delay_gate() {
+        let mut time = NetworkTime::default();
+        time.set_network_frame_rate(1000); // 1ms per frame
+        time.set_message_send_rate(10);
+        time.update_send_rate(32, 1200, Duration::from_millis(100), 2);
+
+        time.set_frame_number(55);
+        time.mark_message_sent();
+        assert!(!time.should_send_message_now());
+    }
 }